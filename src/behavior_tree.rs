@@ -36,6 +36,52 @@ pub fn select<E>(children: Vec<NodePtr<E>>) -> Box<impl BtNode<E>> {
     Box::new(Selector::new(children))
 }
 
+pub fn parallel<E>(
+    children: Vec<NodePtr<E>>,
+    success_threshold: usize,
+    failure_threshold: usize,
+) -> Box<impl BtNode<E>> {
+    Box::new(Parallel::new(children, success_threshold, failure_threshold))
+}
+
+pub fn inverter<E>(mut child: NodePtr<E>) -> Box<impl BtNode<E>> {
+    lambda(move |env| match child.tick(env) {
+        BtState::Success => BtState::Failure,
+        BtState::Failure => BtState::Success,
+        other => other,
+    })
+}
+
+pub fn cooldown<E>(ticks: usize, mut child: NodePtr<E>) -> Box<impl BtNode<E>> {
+    let mut since_success = ticks;
+    lambda(move |env| {
+        if since_success < ticks {
+            since_success += 1;
+            return BtState::Failure;
+        }
+
+        let ret = child.tick(env);
+        if ret == BtState::Success {
+            since_success = 0;
+        }
+        ret
+    })
+}
+
+pub fn retry<E>(max: usize, mut child: NodePtr<E>) -> Box<impl BtNode<E>> {
+    let mut attempts = 0;
+    lambda(move |env| match child.tick(env) {
+        BtState::Failure if attempts < max => {
+            attempts += 1;
+            BtState::Running
+        }
+        other => {
+            attempts = 0;
+            other
+        }
+    })
+}
+
 pub fn run_or_fail<E, P: FnMut(&mut E) -> bool>(mut func: P) -> Box<impl BtNode<E>> {
     let mut state = BtState::NotStarted;
     lambda(move |e| match state {
@@ -159,3 +205,57 @@ impl<E> BtNode<E> for Selector<E> {
         }
     }
 }
+
+struct Parallel<E> {
+    children: Vec<NodePtr<E>>,
+    latched: Vec<BtState>,
+    success_threshold: usize,
+    failure_threshold: usize,
+}
+
+impl<E> Parallel<E> {
+    fn new(
+        children: Vec<NodePtr<E>>,
+        success_threshold: usize,
+        failure_threshold: usize,
+    ) -> Parallel<E> {
+        let latched = vec![BtState::NotStarted; children.len()];
+        Parallel {
+            children,
+            latched,
+            success_threshold,
+            failure_threshold,
+        }
+    }
+
+    fn reset(&mut self) {
+        for state in self.latched.iter_mut() {
+            *state = BtState::NotStarted;
+        }
+    }
+}
+
+impl<E> BtNode<E> for Parallel<E> {
+    fn tick(&mut self, env: &mut E) -> BtState {
+        for (child, latched) in self.children.iter_mut().zip(self.latched.iter_mut()) {
+            if *latched == BtState::NotStarted || *latched == BtState::Running {
+                *latched = child.tick(env);
+            }
+        }
+
+        let successes = self.latched.iter().filter(|&&s| s == BtState::Success).count();
+        let failures = self.latched.iter().filter(|&&s| s == BtState::Failure).count();
+
+        if successes >= self.success_threshold {
+            self.reset();
+            return BtState::Success;
+        }
+
+        if failures >= self.failure_threshold {
+            self.reset();
+            return BtState::Failure;
+        }
+
+        BtState::Running
+    }
+}
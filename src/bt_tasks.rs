@@ -1,6 +1,13 @@
-use behavior_tree::{interrupt, lambda, run_or_fail, select, sequence, BtNode, BtState};
+use behavior_tree::{
+    condition, cooldown, lambda, parallel, retry, run_or_fail, select, sequence, BtNode, BtState,
+};
 use hlt::direction::Direction;
+use hlt::position::Position;
 use hlt::ShipId;
+use spatial_index::ResourceIndexCache;
+use std::cell::RefCell;
+use std::rc::Rc;
+use tactical::tactical;
 use GameState;
 use rand::{thread_rng, Rng};
 
@@ -13,10 +20,9 @@ fn deliver(id: ShipId) -> Box<impl BtNode<GameState>> {
         }
 
         let pos = state.get_ship(id).position;
-        //let dest = state.me().shipyard.position;
-        //let path = state.get_dijkstra_path(pos, dest);
-        //let d = path.first().cloned().unwrap_or(Direction::Still);
-        let d = state.get_return_dir(pos);
+        let dest = state.me().shipyard.position;
+        let path = state.get_astar_path(pos, dest);
+        let d = path.first().cloned().unwrap_or(Direction::Still);
         if !state.try_move_ship(id, d) {
             let d = state.get_return_dir_alternative(pos);
             state.move_ship_or_wait(id, d);
@@ -31,10 +37,9 @@ fn deliver(id: ShipId) -> Box<impl BtNode<GameState>> {
 fn go_home(id: ShipId) -> Box<impl BtNode<GameState>> {
     lambda(move |state: &mut GameState| {
         let pos = state.get_ship(id).position;
-        //let dest = state.me().shipyard.position;
-        //let path = state.get_dijkstra_path(pos, dest);
-        //let d = path.first().cloned().unwrap_or(Direction::Still);
-        let d = state.get_return_dir(pos);
+        let dest = state.me().shipyard.position;
+        let path = state.get_astar_path(pos, dest);
+        let d = path.first().cloned().unwrap_or(Direction::Still);
         let p = pos.directional_offset(d);
 
         if state.game.map.at_position(&p).structure.is_some() {
@@ -50,20 +55,20 @@ fn go_home(id: ShipId) -> Box<impl BtNode<GameState>> {
     })
 }
 
-/*fn go_to(id: ShipId, dest: Position) -> Box<impl BtNode<GameState>> {
+pub fn go_to(id: ShipId, dest: Position) -> Box<impl BtNode<GameState>> {
     lambda(move |state: &mut GameState| {
         if state.get_ship(id).position == dest {
             return BtState::Success;
         }
 
         let pos = state.get_ship(id).position;
-        let path = state.get_dijkstra_path(pos, dest);
+        let path = state.get_astar_path(pos, dest);
         let d = path.first().cloned().unwrap_or(Direction::Still);
         state.move_ship_or_wait(id, d);
 
         BtState::Running
     })
-}*/
+}
 
 fn find_res(id: ShipId) -> Box<impl BtNode<GameState>> {
     lambda(move |state: &mut GameState| {
@@ -76,28 +81,32 @@ fn find_res(id: ShipId) -> Box<impl BtNode<GameState>> {
             return BtState::Success;
         }
 
-        let d = Direction::get_all_options().into_iter()
+        let best = Direction::get_all_options().into_iter()
             .map(|d| (d, state.game.map.normalize(&pos.directional_offset(d))))
             .filter(|(_, p)| state.navi.is_safe(p) || *p == pos)
-            .max_by_key(|(_, p)| (state.get_pheromone(*p) * 1000.0) as i32)
-            .map(|(d, _)| d)
-            .unwrap_or(Direction::Still);
+            .max_by_key(|(_, p)| (state.get_pheromone(*p) * 1000.0) as i32);
+
+        let (d, p) = match best {
+            Some(found) => found,
+            None => return BtState::Failure,
+        };
+
+        if p == pos {
+            // Nothing worth moving toward among the adjacent tiles this probe;
+            // fail so retry() can try again on a later tick.
+            return BtState::Failure;
+        }
 
         state.move_ship(id, d);
 
         BtState::Running
-
-        /*match state.get_nearest_halite_move(pos, SEEK_LIMIT) {
-            Some(d) => {
-                state.move_ship(id, d);
-                BtState::Running
-            }
-            None => BtState::Failure,
-        }*/
     })
 }
 
-fn find_desperate(id: ShipId) -> Box<impl BtNode<GameState>> {
+fn find_desperate(
+    id: ShipId,
+    resource_cache: Rc<RefCell<ResourceIndexCache>>,
+) -> Box<impl BtNode<GameState>> {
     lambda(move |state: &mut GameState| {
         let pos = state.get_ship(id).position;
         let current_halite = state.game.map.at_position(&pos).halite;
@@ -106,8 +115,9 @@ fn find_desperate(id: ShipId) -> Box<impl BtNode<GameState>> {
             return BtState::Success;
         }
 
-        match state.get_nearest_halite_move(pos, 1) {
-            Some(d) => {
+        let mut resource_cache = resource_cache.borrow_mut();
+        match state.nearest_resource(pos, 1, &mut resource_cache) {
+            Some((_, d)) => {
                 state.move_ship(id, d);
                 BtState::Running
             }
@@ -220,24 +230,99 @@ fn desperate(id: ShipId) -> Box<impl BtNode<GameState>> {
 }
 
 pub fn build_dropoff(id: ShipId) -> Box<impl BtNode<GameState>> {
-    run_or_fail(move |state: &mut GameState| state.try_build_dropoff(id))
+    const BUILD_DROPOFF_COOLDOWN: usize = 10;
+
+    cooldown(
+        BUILD_DROPOFF_COOLDOWN,
+        run_or_fail(move |state: &mut GameState| state.try_build_dropoff(id)),
+    )
+}
+
+pub fn route(id: ShipId) -> Box<impl BtNode<GameState>> {
+    let mut tour: Vec<Position> = Vec::new();
+    lambda(move |state: &mut GameState| {
+        if state.get_ship(id).is_full() {
+            tour.clear();
+            return BtState::Success;
+        }
+
+        let pos = state.get_ship(id).position;
+
+        if tour.is_empty() {
+            let dropoff = state.me().shipyard.position;
+            let targets = state.pick_mining_targets(pos, 8);
+            if targets.is_empty() {
+                return BtState::Failure;
+            }
+            tour = state.best_mining_tour(pos, targets, dropoff);
+        }
+
+        if pos == tour[0] {
+            tour.remove(0);
+        }
+
+        let d = match tour.first() {
+            Some(&waypoint) => {
+                let path = state.get_astar_path(pos, waypoint);
+                path.first().cloned().unwrap_or(Direction::Still)
+            }
+            None => Direction::Still,
+        };
+
+        state.move_ship_or_wait(id, d);
+
+        BtState::Running
+    })
+}
+
+const FIND_RES_RETRIES: usize = 3;
+const TACTICAL_ENDGAME_WINDOW: usize = 15;
+
+fn tactical_situation(env: &mut GameState) -> bool {
+    env.rounds_left() <= TACTICAL_ENDGAME_WINDOW
+}
+
+fn go_home_safety_check(id: ShipId) -> Box<impl BtNode<GameState>> {
+    lambda(move |env: &mut GameState| {
+        const GO_HOME_SAFETY_FACTOR: usize = 1;
+
+        let dist = env.get_return_distance(env.get_ship(id).position);
+        let must_go_home = env.rounds_left()
+            <= dist + (env.me().ship_ids.len() * GO_HOME_SAFETY_FACTOR) / (1 + env.me().dropoff_ids.len());
+
+        if must_go_home {
+            BtState::Failure
+        } else {
+            BtState::Running
+        }
+    })
 }
 
-pub fn collector(id: ShipId) -> Box<impl BtNode<GameState>> {
+pub fn collector(
+    id: ShipId,
+    resource_cache: Rc<RefCell<ResourceIndexCache>>,
+) -> Box<impl BtNode<GameState>> {
     select(vec![
-        interrupt(
-            select(vec![
-                sequence(vec![greedy(id), deliver(id)]),
-                find_res(id),
-                sequence(vec![desperate(id), deliver(id)]),
-                find_desperate(id),
-            ]),
-            move |env| {
-                const GO_HOME_SAFETY_FACTOR: usize = 1;
-
-                let dist = env.get_return_distance(env.get_ship(id).position);
-                env.rounds_left() <= dist + (env.me().ship_ids.len() * GO_HOME_SAFETY_FACTOR) / (1 + env.me().dropoff_ids.len())
-            },
+        parallel(
+            vec![
+                select(vec![
+                    sequence(vec![condition(tactical_situation), tactical(id)]),
+                    sequence(vec![
+                        condition(move |env: &mut GameState| {
+                            let pos = env.get_ship(id).position;
+                            env.pick_mining_targets(pos, 2).len() >= 2
+                        }),
+                        route(id),
+                    ]),
+                    sequence(vec![greedy(id), deliver(id)]),
+                    retry(FIND_RES_RETRIES, find_res(id)),
+                    sequence(vec![desperate(id), deliver(id)]),
+                    find_desperate(id, resource_cache),
+                ]),
+                go_home_safety_check(id),
+            ],
+            1,
+            1,
         ),
         go_home(id),
     ])
@@ -0,0 +1,122 @@
+use hlt::direction::Direction;
+use hlt::position::Position;
+use std::cmp::Ordering;
+use std::collections::{BinaryHeap, HashMap};
+use GameState;
+
+const UNSAFE_TILE_PENALTY: i32 = 1000;
+
+#[derive(Copy, Clone, Eq, PartialEq)]
+struct Candidate {
+    f: i32,
+    pos: Position,
+}
+
+impl Ord for Candidate {
+    fn cmp(&self, other: &Self) -> Ordering {
+        // BinaryHeap is a max-heap; reverse so the lowest f comes out first.
+        other.f.cmp(&self.f)
+    }
+}
+
+impl PartialOrd for Candidate {
+    fn partial_cmp(&self, other: &Self) -> Option<Ordering> {
+        Some(self.cmp(other))
+    }
+}
+
+impl GameState {
+    pub fn get_astar_path(&self, start: Position, dest: Position) -> Vec<Direction> {
+        self.astar(start, dest).0
+    }
+
+    pub fn get_astar_cost(&self, start: Position, dest: Position) -> i32 {
+        self.astar(start, dest).1
+    }
+
+    fn astar(&self, start: Position, dest: Position) -> (Vec<Direction>, i32) {
+        let start = self.game.map.normalize(&start);
+        let dest = self.game.map.normalize(&dest);
+
+        if start == dest {
+            return (Vec::new(), 0);
+        }
+
+        let dropoff_positions = self.dropoff_positions();
+
+        let mut open = BinaryHeap::new();
+        let mut g_score: HashMap<Position, i32> = HashMap::new();
+        let mut came_from: HashMap<Position, (Position, Direction)> = HashMap::new();
+
+        g_score.insert(start, 0);
+        open.push(Candidate {
+            f: self.manhattan_distance(&start, &dest),
+            pos: start,
+        });
+
+        while let Some(Candidate { pos, .. }) = open.pop() {
+            if pos == dest {
+                let g = g_score[&pos];
+                return (self.reconstruct_path(&came_from, start, dest), g);
+            }
+
+            let g = g_score[&pos];
+
+            for d in Direction::get_all_cardinals() {
+                let next = self.game.map.normalize(&pos.directional_offset(d));
+
+                let mut step_cost =
+                    self.game.map.at_position(&pos).halite / self.game.constants.move_cost_ratio + 1;
+
+                if !self.navi.is_safe(&next) && !dropoff_positions.contains(&next) {
+                    step_cost += UNSAFE_TILE_PENALTY;
+                }
+
+                let tentative_g = g + step_cost as i32;
+
+                if tentative_g < *g_score.get(&next).unwrap_or(&i32::max_value()) {
+                    g_score.insert(next, tentative_g);
+                    came_from.insert(next, (pos, d));
+                    open.push(Candidate {
+                        f: tentative_g + self.manhattan_distance(&next, &dest),
+                        pos: next,
+                    });
+                }
+            }
+        }
+
+        (Vec::new(), i32::max_value())
+    }
+
+    fn manhattan_distance(&self, from: &Position, to: &Position) -> i32 {
+        self.game.map.calculate_distance(from, to) as i32
+    }
+
+    fn dropoff_positions(&self) -> Vec<Position> {
+        let me = self.me();
+        let mut positions = vec![me.shipyard.position];
+        for dropoff_id in &me.dropoff_ids {
+            positions.push(self.game.dropoffs[dropoff_id].position);
+        }
+        positions
+    }
+
+    fn reconstruct_path(
+        &self,
+        came_from: &HashMap<Position, (Position, Direction)>,
+        start: Position,
+        dest: Position,
+    ) -> Vec<Direction> {
+        let mut path = Vec::new();
+        let mut current = dest;
+
+        while current != start {
+            let (prev, d) = came_from[&current];
+            path.push(d);
+            current = prev;
+        }
+
+        path.reverse();
+        path
+    }
+}
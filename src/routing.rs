@@ -0,0 +1,136 @@
+use hlt::position::Position;
+use std::time::{Duration, Instant};
+use GameState;
+
+const MAX_TOUR_TARGETS: usize = 8;
+const CLUSTER_SEARCH_RADIUS: i32 = 6;
+const TOUR_SEARCH_BUDGET: Duration = Duration::from_millis(20);
+
+impl GameState {
+    pub fn best_mining_tour(
+        &self,
+        start: Position,
+        targets: Vec<Position>,
+        dropoff: Position,
+    ) -> Vec<Position> {
+        if targets.is_empty() {
+            return Vec::new();
+        }
+
+        let deadline = Instant::now() + TOUR_SEARCH_BUDGET;
+        let (start_costs, costs) = self.pairwise_costs(start, &targets, dropoff);
+
+        let mut order: Vec<usize> = (0..targets.len()).collect();
+        let mut best_order = order.clone();
+        let mut best_cost = i32::max_value();
+
+        loop {
+            if let Some(cost) = score_tour(&start_costs, &costs, &order, best_cost) {
+                best_cost = cost;
+                best_order = order.clone();
+            }
+
+            if Instant::now() >= deadline || !next_permutation(&mut order) {
+                break;
+            }
+        }
+
+        best_order.into_iter().map(|i| targets[i]).collect()
+    }
+
+    // Pairwise A* costs between start and every target, and between every
+    // pair of targets and the dropoff, computed once up front so permuting
+    // the tour order doesn't recompute the same leg's cost over and over.
+    // costs[i][j] is target[i] -> target[j]; costs[i][targets.len()] is
+    // target[i] -> dropoff.
+    fn pairwise_costs(
+        &self,
+        start: Position,
+        targets: &[Position],
+        dropoff: Position,
+    ) -> (Vec<i32>, Vec<Vec<i32>>) {
+        let n = targets.len();
+        let start_costs = targets.iter().map(|&t| self.get_astar_cost(start, t)).collect();
+
+        let mut costs = vec![vec![0; n + 1]; n];
+        for i in 0..n {
+            for j in 0..n {
+                if i != j {
+                    costs[i][j] = self.get_astar_cost(targets[i], targets[j]);
+                }
+            }
+            costs[i][n] = self.get_astar_cost(targets[i], dropoff);
+        }
+
+        (start_costs, costs)
+    }
+
+    pub fn pick_mining_targets(&self, pos: Position, count: usize) -> Vec<Position> {
+        let count = count.min(MAX_TOUR_TARGETS);
+        let mut candidates = Vec::new();
+
+        for dy in -CLUSTER_SEARCH_RADIUS..=CLUSTER_SEARCH_RADIUS {
+            for dx in -CLUSTER_SEARCH_RADIUS..=CLUSTER_SEARCH_RADIUS {
+                let p = self.game.map.normalize(&Position {
+                    x: pos.x + dx,
+                    y: pos.y + dy,
+                });
+                if p == pos || !self.navi.is_safe(&p) {
+                    continue;
+                }
+                candidates.push((self.get_pheromone(p), p));
+            }
+        }
+
+        candidates.sort_by(|a, b| b.0.partial_cmp(&a.0).unwrap());
+        candidates.into_iter().take(count).map(|(_, p)| p).collect()
+    }
+}
+
+fn score_tour(start_costs: &[i32], costs: &[Vec<i32>], order: &[usize], best_so_far: i32) -> Option<i32> {
+    let n = order.len();
+    let dropoff_col = n;
+
+    let mut cost = start_costs[order[0]];
+    if cost >= best_so_far {
+        return None;
+    }
+
+    for w in 1..n {
+        cost += costs[order[w - 1]][order[w]];
+        if cost >= best_so_far {
+            return None;
+        }
+    }
+
+    cost += costs[order[n - 1]][dropoff_col];
+    if cost >= best_so_far {
+        return None;
+    }
+
+    Some(cost)
+}
+
+fn next_permutation(order: &mut Vec<usize>) -> bool {
+    let n = order.len();
+    if n < 2 {
+        return false;
+    }
+
+    let mut i = n - 1;
+    while i > 0 && order[i - 1] >= order[i] {
+        i -= 1;
+    }
+    if i == 0 {
+        return false;
+    }
+
+    let mut j = n - 1;
+    while order[j] <= order[i - 1] {
+        j -= 1;
+    }
+
+    order.swap(i - 1, j);
+    order[i..].reverse();
+    true
+}
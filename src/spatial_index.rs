@@ -0,0 +1,214 @@
+use hlt::direction::Direction;
+use hlt::position::Position;
+use GameState;
+
+const MAX_ENTRIES: usize = 8;
+
+// Cache for the resource R-tree, owned by whoever calls nearest_resource
+// (see find_desperate in bt_tasks.rs). The cached index is rebuilt whenever the
+// turn number or min_halite differs from the one it was built for.
+pub struct ResourceIndexCache {
+    entry: Option<(i32, usize, ResourceIndex)>,
+}
+
+impl ResourceIndexCache {
+    pub fn new() -> ResourceIndexCache {
+        ResourceIndexCache { entry: None }
+    }
+}
+
+#[derive(Copy, Clone, Debug)]
+struct Rect {
+    min_x: i32,
+    min_y: i32,
+    max_x: i32,
+    max_y: i32,
+}
+
+impl Rect {
+    fn of_point(p: Position) -> Rect {
+        Rect {
+            min_x: p.x,
+            min_y: p.y,
+            max_x: p.x,
+            max_y: p.y,
+        }
+    }
+
+    fn union(a: Rect, b: Rect) -> Rect {
+        Rect {
+            min_x: a.min_x.min(b.min_x),
+            min_y: a.min_y.min(b.min_y),
+            max_x: a.max_x.max(b.max_x),
+            max_y: a.max_y.max(b.max_y),
+        }
+    }
+
+    fn min_toroidal_distance(&self, p: Position, width: i32, height: i32) -> i32 {
+        axis_distance(p.x, self.min_x, self.max_x, width)
+            + axis_distance(p.y, self.min_y, self.max_y, height)
+    }
+}
+
+fn axis_distance(v: i32, lo: i32, hi: i32, size: i32) -> i32 {
+    if v >= lo && v <= hi {
+        return 0;
+    }
+    let d_lo = wrapped_distance(v, lo, size);
+    let d_hi = wrapped_distance(v, hi, size);
+    d_lo.min(d_hi)
+}
+
+fn wrapped_distance(a: i32, b: i32, size: i32) -> i32 {
+    let d = (a - b).abs();
+    d.min(size - d)
+}
+
+enum Node {
+    Leaf(Vec<(Position, usize)>),
+    Internal(Vec<(Rect, Box<Node>)>),
+}
+
+pub struct ResourceIndex {
+    root: Option<Node>,
+    width: i32,
+    height: i32,
+}
+
+impl ResourceIndex {
+    fn build(mut entries: Vec<(Position, usize)>, width: i32, height: i32) -> ResourceIndex {
+        if entries.is_empty() {
+            return ResourceIndex {
+                root: None,
+                width,
+                height,
+            };
+        }
+
+        entries.sort_by_key(|(p, _)| (p.x, p.y));
+        let mut nodes: Vec<(Rect, Node)> = entries
+            .chunks(MAX_ENTRIES)
+            .map(|chunk| {
+                let rect = chunk
+                    .iter()
+                    .map(|(p, _)| Rect::of_point(*p))
+                    .fold(Rect::of_point(chunk[0].0), Rect::union);
+                (rect, Node::Leaf(chunk.to_vec()))
+            })
+            .collect();
+
+        while nodes.len() > 1 {
+            nodes = nodes
+                .chunks(MAX_ENTRIES)
+                .map(|chunk| {
+                    let rect = chunk
+                        .iter()
+                        .map(|(r, _)| *r)
+                        .fold(chunk[0].0, Rect::union);
+                    let children = chunk
+                        .iter()
+                        .map(|(r, n)| (*r, Box::new(clone_node(n))))
+                        .collect();
+                    (rect, Node::Internal(children))
+                })
+                .collect();
+        }
+
+        ResourceIndex {
+            root: nodes.into_iter().next().map(|(_, n)| n),
+            width,
+            height,
+        }
+    }
+
+    fn nearest(&self, from: Position) -> Option<(Position, usize)> {
+        let root = self.root.as_ref()?;
+        let mut best: Option<(i32, Position, usize)> = None;
+        self.search(root, from, &mut best);
+        best.map(|(_, p, halite)| (p, halite))
+    }
+
+    fn search(&self, node: &Node, from: Position, best: &mut Option<(i32, Position, usize)>) {
+        match node {
+            Node::Leaf(points) => {
+                for &(p, halite) in points {
+                    let d = wrapped_distance(p.x, from.x, self.width)
+                        + wrapped_distance(p.y, from.y, self.height);
+                    if best.map_or(true, |(best_d, _, _)| d < best_d) {
+                        *best = Some((d, p, halite));
+                    }
+                }
+            }
+            Node::Internal(children) => {
+                let mut ordered: Vec<&(Rect, Box<Node>)> = children.iter().collect();
+                ordered.sort_by_key(|(rect, _)| rect.min_toroidal_distance(from, self.width, self.height));
+
+                for (rect, child) in ordered {
+                    let bound = rect.min_toroidal_distance(from, self.width, self.height);
+                    if best.map_or(false, |(best_d, _, _)| bound >= best_d) {
+                        continue;
+                    }
+                    self.search(child, from, best);
+                }
+            }
+        }
+    }
+}
+
+fn clone_node(node: &Node) -> Node {
+    match node {
+        Node::Leaf(points) => Node::Leaf(points.clone()),
+        Node::Internal(children) => Node::Internal(
+            children
+                .iter()
+                .map(|(r, n)| (*r, Box::new(clone_node(n))))
+                .collect(),
+        ),
+    }
+}
+
+impl GameState {
+    pub fn nearest_resource(
+        &self,
+        pos: Position,
+        min_halite: usize,
+        cache: &mut ResourceIndexCache,
+    ) -> Option<(Position, Direction)> {
+        let turn = self.game.turn_number;
+
+        let stale = match &cache.entry {
+            Some((cached_turn, cached_min_halite, _)) => {
+                *cached_turn != turn || *cached_min_halite != min_halite
+            }
+            None => true,
+        };
+
+        if stale {
+            cache.entry = Some((turn, min_halite, self.build_resource_index(min_halite)));
+        }
+
+        let target = cache.entry.as_ref().unwrap().2.nearest(pos)?;
+
+        let path = self.get_astar_path(pos, target.0);
+        let d = path.first().cloned().unwrap_or(Direction::Still);
+        Some((target.0, d))
+    }
+
+    fn build_resource_index(&self, min_halite: usize) -> ResourceIndex {
+        let width = self.game.map.width as i32;
+        let height = self.game.map.height as i32;
+
+        let mut entries = Vec::new();
+        for y in 0..height {
+            for x in 0..width {
+                let p = Position { x, y };
+                let halite = self.game.map.at_position(&p).halite;
+                if halite >= min_halite {
+                    entries.push((p, halite));
+                }
+            }
+        }
+
+        ResourceIndex::build(entries, width, height)
+    }
+}
@@ -0,0 +1,213 @@
+use behavior_tree::{lambda, BtNode, BtState};
+use hlt::direction::Direction;
+use hlt::position::Position;
+use hlt::ShipId;
+use rand::{thread_rng, Rng};
+use std::collections::HashMap;
+use std::time::{Duration, Instant};
+use GameState;
+
+const WINDOW_RADIUS: i32 = 4;
+const SIMULATION_HORIZON: usize = 6;
+const SHIP_LOSS_PENALTY: f64 = 1000.0;
+const EXPLORATION_CONSTANT: f64 = 1.41;
+const SEARCH_BUDGET: Duration = Duration::from_millis(15);
+
+pub fn tactical(id: ShipId) -> Box<impl BtNode<GameState>> {
+    lambda(move |state: &mut GameState| {
+        let pos = state.get_ship(id).position;
+        let cargo = state.get_ship(id).halite;
+        let model = LocalModel::from_state(state, pos);
+
+        let d = mcts_search(&model, pos, cargo, Instant::now() + SEARCH_BUDGET);
+        state.move_ship_or_wait(id, d);
+
+        BtState::Running
+    })
+}
+
+struct LocalModel {
+    halite: HashMap<Position, usize>,
+    extract_ratio: usize,
+    move_cost_ratio: usize,
+    unsafe_tiles: Vec<Position>,
+}
+
+impl LocalModel {
+    fn from_state(state: &GameState, center: Position) -> LocalModel {
+        let mut halite = HashMap::new();
+        let mut unsafe_tiles = Vec::new();
+
+        for dy in -WINDOW_RADIUS..=WINDOW_RADIUS {
+            for dx in -WINDOW_RADIUS..=WINDOW_RADIUS {
+                let p = state.game.map.normalize(&Position {
+                    x: center.x + dx,
+                    y: center.y + dy,
+                });
+                halite.insert(p, state.game.map.at_position(&p).halite);
+                if !state.navi.is_safe(&p) {
+                    unsafe_tiles.push(p);
+                }
+            }
+        }
+
+        LocalModel {
+            halite,
+            extract_ratio: state.game.constants.extract_ratio,
+            move_cost_ratio: state.game.constants.move_cost_ratio,
+            unsafe_tiles,
+        }
+    }
+
+    fn halite_at(&self, p: &Position) -> usize {
+        *self.halite.get(p).unwrap_or(&0)
+    }
+
+    fn is_safe(&self, p: &Position) -> bool {
+        !self.unsafe_tiles.contains(p)
+    }
+
+    fn legal_moves(&self, pos: &Position) -> Vec<Direction> {
+        Direction::get_all_options()
+            .into_iter()
+            .filter(|d| {
+                let p = pos.directional_offset(*d);
+                self.is_safe(&p) || p == *pos
+            })
+            .collect()
+    }
+}
+
+struct TreeNode {
+    visits: u32,
+    value: f64,
+    pos: Position,
+    cargo: usize,
+    depth: usize,
+    untried: Vec<Direction>,
+    children: Vec<(Direction, TreeNode)>,
+}
+
+impl TreeNode {
+    fn new(model: &LocalModel, pos: Position, cargo: usize, depth: usize) -> TreeNode {
+        TreeNode {
+            visits: 0,
+            value: 0.0,
+            pos,
+            cargo,
+            depth,
+            untried: model.legal_moves(&pos),
+            children: Vec::new(),
+        }
+    }
+
+    fn is_fully_expanded(&self) -> bool {
+        self.untried.is_empty()
+    }
+
+    fn best_child_uct(&self) -> usize {
+        let parent_visits = self.visits as f64;
+        self.children
+            .iter()
+            .enumerate()
+            .max_by(|(_, (_, a)), (_, (_, b))| {
+                uct_score(a, parent_visits)
+                    .partial_cmp(&uct_score(b, parent_visits))
+                    .unwrap()
+            })
+            .map(|(i, _)| i)
+            .unwrap()
+    }
+
+    fn most_visited_child(&self) -> Direction {
+        self.children
+            .iter()
+            .max_by_key(|(_, child)| child.visits)
+            .map(|(d, _)| *d)
+            .unwrap_or(Direction::Still)
+    }
+}
+
+fn uct_score(node: &TreeNode, parent_visits: f64) -> f64 {
+    if node.visits == 0 {
+        return f64::INFINITY;
+    }
+    let win_rate = node.value / node.visits as f64;
+    win_rate + EXPLORATION_CONSTANT * (parent_visits.ln() / node.visits as f64).sqrt()
+}
+
+fn mcts_search(model: &LocalModel, start: Position, cargo: usize, deadline: Instant) -> Direction {
+    let mut root = TreeNode::new(model, start, cargo, 0);
+
+    while Instant::now() < deadline {
+        simulate(model, &mut root);
+    }
+
+    root.most_visited_child()
+}
+
+fn simulate(model: &LocalModel, node: &mut TreeNode) -> f64 {
+    node.visits += 1;
+
+    if node.depth >= SIMULATION_HORIZON {
+        let reward = rollout(model, node.pos, node.cargo, 0);
+        node.value += reward;
+        return reward;
+    }
+
+    if !node.is_fully_expanded() {
+        let d = node.untried.pop().unwrap();
+        let (next_pos, next_cargo) = apply_move(model, node.pos, node.cargo, d);
+        let immediate_gain = next_cargo as f64 - node.cargo as f64;
+        let mut child = TreeNode::new(model, next_pos, next_cargo, node.depth + 1);
+        let reward = immediate_gain + rollout(model, next_pos, next_cargo, node.depth + 1);
+        child.visits += 1;
+        child.value += reward;
+        node.children.push((d, child));
+        node.value += reward;
+        return reward;
+    }
+
+    if node.children.is_empty() {
+        let reward = rollout(model, node.pos, node.cargo, node.depth);
+        node.value += reward;
+        return reward;
+    }
+
+    let idx = node.best_child_uct();
+    let reward = simulate(model, &mut node.children[idx].1);
+    node.value += reward;
+    reward
+}
+
+fn apply_move(model: &LocalModel, pos: Position, cargo: usize, d: Direction) -> (Position, usize) {
+    let cost = model.halite_at(&pos) / model.move_cost_ratio;
+    if d == Direction::Still || cargo < cost {
+        let gained = model.halite_at(&pos) / model.extract_ratio;
+        return (pos, cargo + gained);
+    }
+    (pos.directional_offset(d), cargo - cost)
+}
+
+fn rollout(model: &LocalModel, start: Position, start_cargo: usize, start_depth: usize) -> f64 {
+    let mut rng = thread_rng();
+    let mut pos = start;
+    let mut cargo = start_cargo;
+
+    for _ in start_depth..SIMULATION_HORIZON {
+        let options = model.legal_moves(&pos);
+        if options.is_empty() {
+            return start_cargo as f64 - SHIP_LOSS_PENALTY;
+        }
+        let d = options[rng.gen_range(0, options.len())];
+        let (next_pos, next_cargo) = apply_move(model, pos, cargo, d);
+        pos = next_pos;
+        cargo = next_cargo;
+    }
+
+    let mut reward = cargo as f64 - start_cargo as f64;
+    if !model.is_safe(&pos) {
+        reward -= SHIP_LOSS_PENALTY;
+    }
+    reward
+}